@@ -7,6 +7,7 @@ extern crate regex;
 extern crate semver;
 extern crate tempfile;
 extern crate thiserror;
+extern crate toml_edit;
 
 use clap::{Parser, Subcommand, ValueEnum};
 use git2::{DescribeFormatOptions, DescribeOptions, DiffOptions, Repository};
@@ -16,6 +17,7 @@ use regex::Regex;
 use semver::{BuildMetadata, Prerelease, Version};
 use std::{fs, io::Read, path::Path};
 use thiserror::Error;
+use toml_edit::{DocumentMut, Item};
 use VersionHookError::Outdated;
 
 #[derive(Error, Debug)]
@@ -50,6 +52,23 @@ enum VersioningKind {
     SemverCommit(String),
 }
 
+/// How the next version number is chosen from the commits since the latest tag
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum BumpKindArg {
+    /// Always bump patch by one, regardless of commit contents (legacy behavior)
+    Patch,
+    /// Inspect Conventional Commits since the latest tag and pick major/minor/patch
+    Conventional,
+}
+
+/// Severity of change implied by a single Conventional Commit
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum ConventionalBump {
+    Patch,
+    Minor,
+    Major,
+}
+
 #[derive(Debug, Subcommand)]
 enum Commands {
     /// Bump cargo version from latest tag
@@ -57,72 +76,216 @@ enum Commands {
         path: Vec<String>,
         #[arg(long, value_enum)]
         mode: VersioningKindArg,
+        #[arg(long, value_enum, default_value = "patch")]
+        bump: BumpKindArg,
         #[arg(long, action)]
         dry_run: bool,
     },
     /// Check if last release was tagged
     CheckTags {},
+    /// Generate a Markdown changelog grouped by release tag
+    Changelog {
+        /// Only include commits whose conventional scope matches this name
+        #[arg(long)]
+        scope: Option<String>,
+        /// Write the changelog to this path instead of stdout
+        #[arg(long)]
+        output: Option<String>,
+    },
+}
+
+// Find the `[package]` table in a parsed manifest.
+fn locate_package_table(doc: &DocumentMut) -> Result<&Item, String> {
+    doc.get("package")
+        .ok_or_else(|| String::from("`[package]` section not found in Cargo.toml"))
+}
+
+// Find the `package.version` item in a parsed manifest.
+fn locate_version_item(doc: &DocumentMut) -> Result<&Item, String> {
+    locate_package_table(doc)?
+        .get("version")
+        .ok_or_else(|| String::from("version number not found"))
+}
+
+// A workspace-inherited version shows up as either the dotted-key form
+// `version.workspace = true` (an implicit table) or the explicit inline
+// table `version = { workspace = true }`.
+fn version_item_is_workspace_inherited(item: &Item) -> bool {
+    if let Some(table) = item.as_table() {
+        return table.get("workspace").and_then(Item::as_bool) == Some(true);
+    }
+    if let Some(inline) = item.as_inline_table() {
+        return inline.get("workspace").and_then(|v| v.as_bool()) == Some(true);
+    }
+    false
 }
 
 fn replace_version(path: &str, ver: &str) -> anyhow::Result<()> {
     let contents = fs::read_to_string(path)?;
-    let re = Regex::new(r#"(?m)^version = ".+""#)
-        .context("could not parse version number from Cargo.toml")?;
-    let replaced = re
-        .replace(&contents, format!(r#"version = "{}""#, ver))
-        .into_owned();
-    fs::write(path, replaced).with_context(|| format!("Error writing `{}`", path))
+    let mut doc = contents
+        .parse::<DocumentMut>()
+        .with_context(|| format!("could not parse `{}`", path))?;
+
+    let is_inherited = locate_version_item(&doc)
+        .map(version_item_is_workspace_inherited)
+        .map_err(VersionHookError::Other)?;
+    if is_inherited {
+        return Err(VersionHookError::Other(format!(
+            "`{}` inherits its version via `version.workspace = true`; update the workspace root Cargo.toml instead",
+            path
+        ))
+        .into());
+    }
+
+    let old_decor = doc["package"]["version"]
+        .as_value()
+        .map(|v| v.decor().clone());
+    let mut new_item = toml_edit::value(ver);
+    if let Some(decor) = old_decor {
+        *new_item.as_value_mut().unwrap().decor_mut() = decor;
+    }
+    doc["package"]["version"] = new_item;
+    fs::write(path, doc.to_string()).with_context(|| format!("Error writing `{}`", path))
 }
 
 fn parse_cargo_version(contents: &str) -> Result<Version, String> {
-    let re = Regex::new(r#"(?m)^version = "(.+)""#).unwrap();
-    let ver_captures = re
-        .captures_iter(contents)
-        .next()
-        .ok_or(String::from("version number not found"))?;
-    let version = &ver_captures[1];
+    let doc = contents
+        .parse::<DocumentMut>()
+        .map_err(|err| format!("error parsing Cargo.toml: {}", err))?;
+    let version_item = locate_version_item(&doc)?;
+
+    if let Some(version_str) = version_item.as_str() {
+        return Version::parse(version_str).or(Err(format!(
+            "error parsing version from Cargo.toml {}",
+            version_str
+        )));
+    }
 
-    Version::parse(version).or(Err(format!(
-        "error parsing version from Cargo.toml {}",
-        version
-    )))
+    if version_item_is_workspace_inherited(version_item) {
+        return Err(String::from(
+            "version is inherited via `version.workspace = true`; read it from the workspace root Cargo.toml instead",
+        ));
+    }
+
+    Err(format!(
+        "unsupported `package.version` value in Cargo.toml: {}",
+        version_item
+    ))
 }
 
-fn get_cargo_version(repo: &Repository) -> anyhow::Result<Version> {
-    let cargo_version = match get_cargo_toml(repo) {
+fn parse_cargo_name(contents: &str) -> Result<String, String> {
+    let doc = contents
+        .parse::<DocumentMut>()
+        .map_err(|err| format!("error parsing Cargo.toml: {}", err))?;
+    locate_package_table(&doc)?
+        .get("name")
+        .and_then(Item::as_str)
+        .map(|s| s.to_string())
+        .ok_or_else(|| String::from("package name not found"))
+}
+
+fn get_cargo_version(repo: &Repository, manifest_path: &str) -> anyhow::Result<Version> {
+    let cargo_version = match get_cargo_toml(repo, manifest_path) {
         Ok(contents) => parse_cargo_version(&contents),
-        Err(err) => Err(format!("Error reading Cargo.toml`: {}", err)),
+        Err(err) => Err(format!("Error reading {}: {}", manifest_path, err)),
+    };
+    cargo_version.map_err(|err| VersionHookError::Other(err).into())
+}
+
+fn get_cargo_name(repo: &Repository, manifest_path: &str) -> anyhow::Result<String> {
+    let cargo_name = match get_cargo_toml(repo, manifest_path) {
+        Ok(contents) => parse_cargo_name(&contents),
+        Err(err) => Err(format!("Error reading {}: {}", manifest_path, err)),
     };
-    cargo_version.map_err(|err| VersionHookError::Other(format!("{}", err)).into())
+    cargo_name.map_err(|err| VersionHookError::Other(err).into())
 }
 
 fn open_repository(path: &str) -> anyhow::Result<Repository> {
     Repository::discover(path).context("Error openning repository")
 }
 
-fn get_latest_tag(repo: &Repository, abbrv_size: u32) -> anyhow::Result<Version> {
+// Describe HEAD against the latest matching tag. `tag_prefix`, when set,
+// narrows the match to a single crate's tag namespace (e.g. `my-crate-v`)
+// for workspaces where every member is tagged independently.
+fn describe_latest_tag(
+    repo: &Repository,
+    abbrv_size: u32,
+    tag_prefix: Option<&str>,
+) -> anyhow::Result<String> {
     let mut opts = DescribeOptions::new();
     let opts = opts.describe_tags();
+    if let Some(prefix) = tag_prefix {
+        opts.pattern(&format!("{}*", prefix));
+    }
 
     let mut format_opts = DescribeFormatOptions::new();
     let format_opts = format_opts.abbreviated_size(abbrv_size);
 
-    let version_str = repo
-        .describe(opts)
+    repo.describe(opts)
         .context("could not get tag")?
         .format(Some(format_opts))
-        .unwrap();
+        .context("could not format tag description")
+}
 
+fn get_latest_tag(
+    repo: &Repository,
+    abbrv_size: u32,
+    tag_prefix: Option<&str>,
+) -> anyhow::Result<Version> {
+    let version_str = describe_latest_tag(repo, abbrv_size, tag_prefix)?;
     log::debug!("Found git version string {}", &version_str);
-    let version_number = version_str.strip_prefix('v').unwrap_or(&version_str);
+    let version_number = match tag_prefix {
+        Some(prefix) => version_str.strip_prefix(prefix).unwrap_or(&version_str),
+        None => version_str.strip_prefix('v').unwrap_or(&version_str),
+    };
 
     Version::parse(version_number)
         .with_context(|| format!("error parsing version from git tag {}", version_str))
 }
 
+// Whether `commit` changed any file under `scope` relative to its first
+// parent (a root commit is treated as changing everything).
+fn commit_touches_scope(
+    repo: &Repository,
+    commit: &git2::Commit,
+    scope: &str,
+) -> Result<bool, git2::Error> {
+    let new_tree = commit.tree()?;
+    let old_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree()?),
+        Err(_) => None,
+    };
+
+    let mut diff_options = DiffOptions::new();
+    diff_options.pathspec(scope);
+    let diff = repo.diff_tree_to_tree(old_tree.as_ref(), Some(&new_tree), Some(&mut diff_options))?;
+    Ok(diff.deltas().next().is_some())
+}
+
+// Count commits reachable from HEAD but not from `since`, i.e. how many
+// commits have landed on top of a tag. `scope`, when set, only counts
+// commits that touched that workspace member's directory.
+fn commits_since(repo: &Repository, since: git2::Oid, scope: Option<&str>) -> anyhow::Result<i32> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push_head()?;
+    revwalk.hide(since)?;
+
+    let mut count = 0;
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        if let Some(scope) = scope {
+            if !commit_touches_scope(repo, &commit, scope)? {
+                continue;
+            }
+        }
+        count += 1;
+    }
+    Ok(count)
+}
+
 fn make_dev_prerelease(
-    pre: Prerelease,
     mode: VersioningKind,
+    n_commits_from_last_tag: i32,
     is_dirty: bool,
 ) -> anyhow::Result<Prerelease> {
     let mk_prerelease_str = |n_commits, mode| -> String {
@@ -135,19 +298,6 @@ fn make_dev_prerelease(
         }
     };
 
-    if pre.is_empty() {
-        return Ok(Prerelease::new(&mk_prerelease_str(1, mode)).unwrap());
-    }
-    let pre_str = pre.as_str();
-    let pre_parts: Vec<&str> = pre.split('-').collect();
-
-    let (n_commits_from_last_tag, _last_commit) = match pre_parts[..] {
-        [n_commits, last_commit] => n_commits
-            .parse::<i32>()
-            .and_then(|parsed| Ok((parsed, last_commit)))
-            .with_context(|| format!("can't create dev prerelease from tag {}", pre_str)),
-        _ => Err(VersionHookError::Other("wrong tag format".to_string()).into()),
-    }?;
     let new_pre_str = if is_dirty {
         mk_prerelease_str(n_commits_from_last_tag + 1, mode)
     } else {
@@ -157,8 +307,10 @@ fn make_dev_prerelease(
         .with_context(|| format!("prerelease string {} is not valid", &new_pre_str))
 }
 
-// Check if repo is in dirty state (some files were modified)
-fn is_repo_dirty(repo: &Repository, filetype: Option<&str>) -> bool {
+// Check if repo is in dirty state (some files were modified). `scope`, when
+// set, restricts this to files under a single workspace member's directory,
+// so staging a change in one crate doesn't mark every other member dirty.
+fn is_repo_dirty(repo: &Repository, filetype: Option<&str>, scope: Option<&str>) -> bool {
     for entry in repo.statuses(None).unwrap().into_iter() {
         if let Some(extension) = filetype {
             if let Some(s) = entry.path() {
@@ -169,6 +321,12 @@ fn is_repo_dirty(repo: &Repository, filetype: Option<&str>) -> bool {
                 continue;
             };
         };
+        if let Some(scope) = scope {
+            match entry.path() {
+                Some(s) if s.starts_with(&format!("{}/", scope)) => {}
+                _ => continue,
+            }
+        };
         match entry.status() {
             git2::Status::IGNORED | git2::Status::WT_NEW => continue,
             _ => return true,
@@ -177,14 +335,14 @@ fn is_repo_dirty(repo: &Repository, filetype: Option<&str>) -> bool {
     false
 }
 
-// get cargo.toml from staging area
+// get a manifest from the staging area
 
-fn get_cargo_toml(repo: &Repository) -> Result<String, String> {
+fn get_cargo_toml(repo: &Repository, manifest_path: &str) -> Result<String, String> {
     let index = repo
         .index()
         .unwrap()
-        .get_path(Path::new("Cargo.toml"), 0)
-        .unwrap();
+        .get_path(Path::new(manifest_path), 0)
+        .ok_or_else(|| format!("`{}` not found in index", manifest_path))?;
     let blob = repo.find_blob(index.id).unwrap();
     let mut content = String::new();
     blob.content()
@@ -193,21 +351,128 @@ fn get_cargo_toml(repo: &Repository) -> Result<String, String> {
     Ok(content)
 }
 
+// Parse the `[workspace] members = [...]` array from a root manifest. If the
+// manifest also declares a `[package]` (a "mixed" workspace manifest, the
+// common case for single-crate repos), the root itself (".") is a member too.
+fn get_workspace_members(root_manifest: &str) -> Result<Vec<String>, String> {
+    let doc = root_manifest
+        .parse::<DocumentMut>()
+        .map_err(|err| format!("error parsing Cargo.toml: {}", err))?;
+
+    let mut members = Vec::new();
+    if doc.get("package").is_some() {
+        members.push(".".to_string());
+    }
+
+    if let Some(list) = doc
+        .get("workspace")
+        .and_then(|workspace| workspace.get("members"))
+        .and_then(Item::as_array)
+    {
+        members.extend(list.iter().filter_map(|v| v.as_str()).map(|s| s.to_string()));
+    }
+    Ok(members)
+}
+
+// Expand a single trailing `/*` glob (as in `members = ["crates/*"]`) into
+// the member directories that actually contain a Cargo.toml. Patterns
+// without a trailing `/*` are taken as literal member paths.
+fn expand_member_globs(repo_root: &Path, patterns: &[String]) -> anyhow::Result<Vec<String>> {
+    let mut members = Vec::new();
+    for pattern in patterns {
+        match pattern.strip_suffix("/*") {
+            Some(prefix) => {
+                let dir = repo_root.join(prefix);
+                let mut expanded: Vec<String> = fs::read_dir(&dir)
+                    .with_context(|| format!("Error reading `{}`", dir.display()))?
+                    .filter_map(Result::ok)
+                    .filter(|entry| entry.path().join("Cargo.toml").is_file())
+                    .map(|entry| format!("{}/{}", prefix, entry.file_name().to_string_lossy()))
+                    .collect();
+                expanded.sort();
+                members.extend(expanded);
+            }
+            None => members.push(pattern.clone()),
+        }
+    }
+    Ok(members)
+}
+
 fn run_sem_ver(
-    _paths: &[String],
+    paths: &[String],
     dry_run: bool,
     mode_arg: VersioningKindArg,
+    bump_arg: BumpKindArg,
 ) -> anyhow::Result<()> {
-    let path = String::from("Cargo.toml");
-    let repo = open_repository(&path)?;
+    let repo = open_repository(".")?;
     log::debug!("Opened repository at {}", &repo.path().to_str().unwrap());
-    run_sem_ver_repo(&repo, dry_run, mode_arg, Some("rs"))
+    run_sem_ver_workspace(&repo, paths, dry_run, mode_arg, bump_arg)
+}
+
+// Discover every workspace member manifest (or just the root one, for a
+// plain single-crate repo) and bump whichever of `paths` were selected
+// (all of them, if `paths` is empty).
+fn run_sem_ver_workspace(
+    repo: &Repository,
+    paths: &[String],
+    dry_run: bool,
+    mode_arg: VersioningKindArg,
+    bump_arg: BumpKindArg,
+) -> anyhow::Result<()> {
+    let root_manifest = get_cargo_toml(repo, "Cargo.toml")
+        .map_err(|err| VersionHookError::Other(format!("Error reading Cargo.toml: {}", err)))?;
+    let repo_root = repo
+        .workdir()
+        .ok_or_else(|| VersionHookError::Other("repository has no working directory".to_string()))?;
+    let members = expand_member_globs(
+        repo_root,
+        &get_workspace_members(&root_manifest).map_err(VersionHookError::Other)?,
+    )?;
+    let is_workspace = members.len() > 1;
+
+    let selected: Vec<&String> = if paths.is_empty() {
+        members.iter().collect()
+    } else {
+        members.iter().filter(|member| paths.contains(member)).collect()
+    };
+
+    let mut last_err = None;
+    for member in selected {
+        let manifest_path = if member == "." {
+            "Cargo.toml".to_string()
+        } else {
+            format!("{}/Cargo.toml", member)
+        };
+        let tag_prefix = if is_workspace {
+            Some(format!("{}-v", get_cargo_name(repo, &manifest_path)?))
+        } else {
+            None
+        };
+
+        if let Err(err) = run_sem_ver_repo(
+            repo,
+            &manifest_path,
+            tag_prefix.as_deref(),
+            dry_run,
+            mode_arg,
+            bump_arg,
+            Some("rs"),
+        ) {
+            last_err = Some(err);
+        }
+    }
+
+    match last_err {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
 }
 
 fn check_rs_files_changed(
     repo: &Repository,
     old_commit: &str,
     new_commit: &str,
+    scope: Option<&str>,
 ) -> Result<bool, git2::Error> {
     let old_commit = repo.revparse_single(old_commit)?;
     let new_commit = repo.find_commit(repo.revparse_single(new_commit)?.id())?;
@@ -217,6 +482,9 @@ fn check_rs_files_changed(
 
     let mut diff_options = DiffOptions::new();
     diff_options.include_typechange(true).ignore_filemode(false);
+    if let Some(scope) = scope {
+        diff_options.pathspec(scope);
+    }
 
     let diff = repo.diff_tree_to_tree(Some(&old_tree), Some(&new_tree), Some(&mut diff_options))?;
 
@@ -233,27 +501,31 @@ fn check_rs_files_changed(
 
 fn run_sem_ver_repo(
     repo: &Repository,
+    manifest_path: &str,
+    tag_prefix: Option<&str>,
     dry_run: bool,
     mode_arg: VersioningKindArg,
+    bump_arg: BumpKindArg,
     filetype: Option<&str>,
 ) -> anyhow::Result<()> {
     let head_ref = get_head_ref(repo);
 
     log::debug!("repo HEAD is at {}", &head_ref[0..5]);
 
-    let sem_ver = get_latest_tag(repo, 4)?;
+    let sem_ver = get_latest_tag(repo, 4, tag_prefix)?;
     log::debug!("Parsed git version {}", sem_ver);
-    let cargo_ver = get_cargo_version(repo)?;
+    let cargo_ver = get_cargo_version(repo, manifest_path)?;
     //let mode = VersioningKind::SemverCommit((&head_ref[0..5]).to_string());
 
-    let is_dirty = is_repo_dirty(repo, filetype);
+    let member_scope = manifest_path.strip_suffix("/Cargo.toml");
+    let is_dirty = is_repo_dirty(repo, filetype, member_scope);
 
-    let latest_tag_str = get_latest_tag(repo, 0)?.to_string();
+    let latest_tag_str = describe_latest_tag(repo, 0, tag_prefix)?;
 
     log::debug!("latest tag is {}", &latest_tag_str);
 
     let changed_from_last_version =
-        check_rs_files_changed(repo, &latest_tag_str, "HEAD").unwrap_or(true);
+        check_rs_files_changed(repo, &latest_tag_str, "HEAD", member_scope).unwrap_or(true);
 
     if !is_dirty && !changed_from_last_version {
         println!("No rust files changed since last tag {}", latest_tag_str);
@@ -265,20 +537,38 @@ fn run_sem_ver_repo(
         VersioningKindArg::Semver => VersioningKind::Semver,
         VersioningKindArg::SemverCommit => VersioningKind::SemverCommit(head_ref[0..5].to_string()),
     };
+    let tag_commit = repo
+        .revparse_single(&latest_tag_str)?
+        .peel_to_commit()?
+        .id();
+    let (major, minor, patch) = match bump_arg {
+        BumpKindArg::Patch => (sem_ver.major, sem_ver.minor, sem_ver.patch + 1),
+        BumpKindArg::Conventional => {
+            let bump_level = conventional_bump_level(repo, tag_commit, member_scope)?;
+            bump_semver(sem_ver.major, sem_ver.minor, sem_ver.patch, bump_level)
+        }
+    };
     let new_version = Version {
-        major: sem_ver.major,
-        minor: sem_ver.minor,
-        patch: sem_ver.patch + 1,
-        pre: make_dev_prerelease(sem_ver.pre, mode, is_dirty)?,
+        major,
+        minor,
+        patch,
+        pre: make_dev_prerelease(mode, commits_since(repo, tag_commit, member_scope)?, is_dirty)?,
         build: BuildMetadata::EMPTY,
     };
     if cargo_ver < new_version {
         if dry_run {
-            println!("Created version number {} (dry-run)", new_version);
+            println!(
+                "Created version number {} for {} (dry-run)",
+                new_version, manifest_path
+            );
         } else {
-            println!("Created version number {}", new_version);
+            println!("Created version number {} for {}", new_version, manifest_path);
             replace_version(
-                repo.workdir().unwrap().join("Cargo.toml").to_str().unwrap(),
+                repo.workdir()
+                    .unwrap()
+                    .join(manifest_path)
+                    .to_str()
+                    .unwrap(),
                 &format!("{}", new_version),
             )?;
         }
@@ -288,16 +578,290 @@ fn run_sem_ver_repo(
         }
         .into())
     } else {
-        println!("Version number {} is up-to-date", cargo_ver);
+        println!("Version number {} is up-to-date for {}", cargo_ver, manifest_path);
         Ok(())
     }
 }
 
+/// A commit summary parsed against the Conventional Commits grammar
+/// `type(optional-scope)!: description`.
+struct ConventionalCommit {
+    commit_type: String,
+    scope: Option<String>,
+    description: String,
+    breaking: bool,
+}
+
+fn parse_conventional_commit(summary: &str) -> Option<ConventionalCommit> {
+    static RE: std::sync::LazyLock<Regex> = std::sync::LazyLock::new(|| {
+        Regex::new(r"^(?P<type>[a-zA-Z]+)(\((?P<scope>[^)]+)\))?(?P<bang>!)?:\s*(?P<desc>.+)$")
+            .unwrap()
+    });
+    let caps = RE.captures(summary)?;
+    Some(ConventionalCommit {
+        commit_type: caps["type"].to_string(),
+        scope: caps.name("scope").map(|m| m.as_str().to_string()),
+        description: caps["desc"].to_string(),
+        breaking: caps.name("bang").is_some(),
+    })
+}
+
+// Classify a single commit message against the Conventional Commits grammar.
+// Commits that don't match the grammar are treated as a patch-level change,
+// unless their body carries a `BREAKING CHANGE:` footer (then major).
+fn classify_commit(summary: &str, body: &str) -> ConventionalBump {
+    let breaking_footer = body.contains("BREAKING CHANGE:");
+    match parse_conventional_commit(summary) {
+        Some(commit) if commit.breaking || breaking_footer => ConventionalBump::Major,
+        Some(commit) if commit.commit_type == "feat" => ConventionalBump::Minor,
+        _ if breaking_footer => ConventionalBump::Major,
+        _ => ConventionalBump::Patch,
+    }
+}
+
+// Walk every commit reachable from HEAD but not from `since` and return the
+// highest-severity Conventional Commit change found among them. `scope`,
+// when set, only considers commits that touched that workspace member's
+// directory, so a breaking change in one crate doesn't bump another.
+fn conventional_bump_level(
+    repo: &Repository,
+    since: git2::Oid,
+    scope: Option<&str>,
+) -> anyhow::Result<ConventionalBump> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push_head()?;
+    revwalk.hide(since)?;
+
+    let mut highest = ConventionalBump::Patch;
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        if let Some(scope) = scope {
+            if !commit_touches_scope(repo, &commit, scope)? {
+                continue;
+            }
+        }
+        let summary = commit.summary().unwrap_or_default();
+        let body = commit.body().unwrap_or_default();
+        let bump = classify_commit(summary, body);
+        if bump > highest {
+            highest = bump;
+        }
+    }
+    Ok(highest)
+}
+
+// Crates below 1.0.0 haven't committed to API stability yet, so by
+// convention (the same one cargo-smart-release encodes as
+// `is_pre_release_version`) a breaking change only bumps minor and a
+// feature only bumps patch.
+fn is_pre_release_version(major: u64) -> bool {
+    major == 0
+}
+
+fn demote_for_pre_release(bump: ConventionalBump) -> ConventionalBump {
+    match bump {
+        ConventionalBump::Major => ConventionalBump::Minor,
+        ConventionalBump::Minor => ConventionalBump::Patch,
+        ConventionalBump::Patch => ConventionalBump::Patch,
+    }
+}
+
+fn bump_semver(major: u64, minor: u64, patch: u64, bump: ConventionalBump) -> (u64, u64, u64) {
+    let bump = if is_pre_release_version(major) {
+        demote_for_pre_release(bump)
+    } else {
+        bump
+    };
+    match bump {
+        ConventionalBump::Major => (major + 1, 0, 0),
+        ConventionalBump::Minor => (major, minor + 1, 0),
+        ConventionalBump::Patch => (major, minor, patch + 1),
+    }
+}
+
 fn get_head_ref(repo: &Repository) -> String {
     let revspec = repo.revparse("HEAD").unwrap();
     format!("{}", revspec.from().unwrap().id())
 }
 
+/// Conventional Commit types rendered as changelog subsections, in the
+/// order git-cliff's default config uses. Types not listed here still get
+/// their own subsection, just appended afterwards in alphabetical order.
+const CHANGELOG_SECTIONS: &[(&str, &str)] = &[
+    ("feat", "Features"),
+    ("fix", "Bug Fixes"),
+    ("perf", "Performance"),
+    ("docs", "Documentation"),
+    ("refactor", "Refactor"),
+    ("style", "Styling"),
+    ("test", "Testing"),
+];
+
+fn changelog_section_title(commit_type: &str) -> String {
+    CHANGELOG_SECTIONS
+        .iter()
+        .find(|(ty, _)| *ty == commit_type)
+        .map(|(_, title)| title.to_string())
+        .unwrap_or_else(|| {
+            let mut chars = commit_type.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+                None => commit_type.to_string(),
+            }
+        })
+}
+
+struct ChangelogEntry {
+    commit_type: String,
+    short_sha: String,
+    description: String,
+}
+
+// Collect conventional commits reachable from `until` but not from `since`,
+// optionally narrowed to a single conventional scope.
+fn changelog_entries(
+    repo: &Repository,
+    since: Option<git2::Oid>,
+    until: git2::Oid,
+    scope: Option<&str>,
+) -> anyhow::Result<Vec<ChangelogEntry>> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(until)?;
+    if let Some(since) = since {
+        revwalk.hide(since)?;
+    }
+
+    let mut entries = Vec::new();
+    for oid in revwalk {
+        let oid = oid?;
+        let commit = repo.find_commit(oid)?;
+        let summary = commit.summary().unwrap_or_default();
+        let Some(parsed) = parse_conventional_commit(summary) else {
+            continue;
+        };
+        if let Some(scope) = scope {
+            if parsed.scope.as_deref() != Some(scope) {
+                continue;
+            }
+        }
+        let short_sha = commit
+            .as_object()
+            .short_id()?
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        entries.push(ChangelogEntry {
+            commit_type: parsed.commit_type,
+            short_sha,
+            description: parsed.description,
+        });
+    }
+    Ok(entries)
+}
+
+fn write_changelog_section(out: &mut String, entries: &[ChangelogEntry]) {
+    let mut grouped: std::collections::BTreeMap<&str, Vec<&ChangelogEntry>> =
+        std::collections::BTreeMap::new();
+    for entry in entries {
+        grouped
+            .entry(entry.commit_type.as_str())
+            .or_default()
+            .push(entry);
+    }
+
+    let known_types: Vec<&str> = CHANGELOG_SECTIONS.iter().map(|(ty, _)| *ty).collect();
+    let mut ordered_types: Vec<&str> = known_types
+        .iter()
+        .copied()
+        .filter(|ty| grouped.contains_key(ty))
+        .collect();
+    ordered_types.extend(grouped.keys().filter(|ty| !known_types.contains(ty)));
+
+    for commit_type in ordered_types {
+        out.push_str(&format!("### {}\n\n", changelog_section_title(commit_type)));
+        for entry in &grouped[commit_type] {
+            out.push_str(&format!(
+                "- {} ({})\n",
+                entry.description, entry.short_sha
+            ));
+        }
+        out.push('\n');
+    }
+}
+
+// Convert a `git2::Time` to a `YYYY-MM-DD` string without pulling in a date
+// dependency, using Howard Hinnant's civil_from_days algorithm (public domain).
+fn format_commit_date(time: git2::Time) -> String {
+    let local_seconds = time.seconds() + i64::from(time.offset_minutes()) * 60;
+    let days = local_seconds.div_euclid(86400);
+
+    let z = days + 719468;
+    let era = if z >= 0 { z } else { z - 146096 } / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let y = yoe + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = if m <= 2 { y + 1 } else { y };
+
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+fn render_changelog(repo: &Repository, scope: Option<&str>) -> anyhow::Result<String> {
+    let mut tags: Vec<(Version, git2::Oid, git2::Time)> = Vec::new();
+    for name in repo.tag_names(None)?.iter().flatten() {
+        let version_str = name.strip_prefix('v').unwrap_or(name);
+        let Ok(version) = Version::parse(version_str) else {
+            continue;
+        };
+        let commit = repo.revparse_single(name)?.peel_to_commit()?;
+        tags.push((version, commit.id(), commit.time()));
+    }
+    tags.sort_by(|a, b| b.0.cmp(&a.0));
+
+    let mut out = String::new();
+
+    let head_oid = repo.revparse_single("HEAD")?.peel_to_commit()?.id();
+    let newest_tag_oid = tags.first().map(|(_, oid, _)| *oid);
+    if newest_tag_oid != Some(head_oid) {
+        let unreleased = changelog_entries(repo, newest_tag_oid, head_oid, scope)?;
+        if !unreleased.is_empty() {
+            out.push_str("## Unreleased\n\n");
+            write_changelog_section(&mut out, &unreleased);
+        }
+    }
+
+    for i in 0..tags.len() {
+        let (version, oid, time) = &tags[i];
+        let since = tags.get(i + 1).map(|(_, oid, _)| *oid);
+        let entries = changelog_entries(repo, since, *oid, scope)?;
+        out.push_str(&format!(
+            "## {} - {}\n\n",
+            version,
+            format_commit_date(*time)
+        ));
+        write_changelog_section(&mut out, &entries);
+    }
+
+    Ok(out)
+}
+
+fn run_changelog(scope: Option<String>, output: Option<String>) -> anyhow::Result<()> {
+    let repo = open_repository(".")?;
+    let changelog = render_changelog(&repo, scope.as_deref())?;
+    match output {
+        Some(path) => {
+            fs::write(&path, changelog).with_context(|| format!("Error writing `{}`", path))
+        }
+        None => {
+            print!("{}", changelog);
+            Ok(())
+        }
+    }
+}
+
 fn run_check_tags() -> anyhow::Result<()> {
     let path = String::from(".");
     let repo = open_repository(&path)?;
@@ -308,7 +872,7 @@ fn run_check_tags() -> anyhow::Result<()> {
 }
 
 fn run_check_tags_repo(repo: &Repository) -> Result<(), String> {
-    if !is_repo_dirty(repo, None) {
+    if !is_repo_dirty(repo, None, None) {
         println!("No changes detected");
         return Ok(());
     }
@@ -321,7 +885,7 @@ fn run_check_tags_repo(repo: &Repository) -> Result<(), String> {
         .or(Err("Error reading file from index.".to_string()))?;
     let cargo_version = parse_cargo_version(&content)?;
     log::debug!("Found cargo version {}", &cargo_version);
-    let sem_ver = get_latest_tag(repo, 0).map_err(|err| format!("{}", err))?;
+    let sem_ver = get_latest_tag(repo, 0, None).map_err(|err| format!("{}", err))?;
     log::debug!("Current repo version {}", &sem_ver);
 
     if cargo_version.pre.is_empty() && sem_ver < cargo_version {
@@ -337,9 +901,11 @@ fn main() {
         Commands::Bump {
             path,
             mode,
+            bump,
             dry_run,
-        } => run_sem_ver(&path, dry_run, mode),
+        } => run_sem_ver(&path, dry_run, mode, bump),
         Commands::CheckTags {} => run_check_tags(),
+        Commands::Changelog { scope, output } => run_changelog(scope, output),
     };
 
     let exit_code = match result {
@@ -362,7 +928,11 @@ mod tests {
     use tempfile::TempDir;
     use Repository;
 
-    use crate::{run_check_tags_repo, run_sem_ver_repo, VersioningKindArg};
+    use crate::{
+        get_workspace_members, parse_cargo_version, render_changelog, replace_version,
+        run_check_tags_repo, run_sem_ver_repo, run_sem_ver_workspace, BumpKindArg,
+        VersioningKindArg,
+    };
 
     pub fn commit(repo: &Repository, index: &mut Index, msg: &str) {
         let id = index.write_tree().unwrap();
@@ -396,8 +966,15 @@ mod tests {
     }
 
     fn setup_repo(td: &TempDir, repo: &Repository) {
+        setup_repo_with_version(td, repo, "0.1.0");
+    }
+
+    fn setup_repo_with_version(td: &TempDir, repo: &Repository, version: &str) {
         let mut index = repo.index().unwrap();
-        let cargo_contents = "[package]\nname = \"test package\"\nversion = \"0.1.0\"\n";
+        let cargo_contents = format!(
+            "[package]\nname = \"test package\"\nversion = \"{}\"\n",
+            version
+        );
         for n in 0..8 {
             let name = format!("f{n}.rs");
             File::create(&td.path().join(&name))
@@ -415,7 +992,7 @@ mod tests {
         commit(repo, &mut index, "another commit");
         let sig = repo.signature().unwrap();
         repo.tag(
-            "0.1.0",
+            version,
             &repo.revparse_single("HEAD").unwrap(),
             &sig,
             "initial version",
@@ -429,7 +1006,16 @@ mod tests {
         let (td, repo) = repo_init();
         setup_repo(&td, &repo);
         assert!(run_check_tags_repo(&repo).is_ok());
-        assert!(run_sem_ver_repo(&repo, true, VersioningKindArg::Semver, None).is_ok());
+        assert!(run_sem_ver_repo(
+            &repo,
+            "Cargo.toml",
+            None,
+            true,
+            VersioningKindArg::Semver,
+            BumpKindArg::Patch,
+            None
+        )
+        .is_ok());
     }
 
     #[test]
@@ -444,11 +1030,29 @@ mod tests {
             .unwrap();
         index.add_path(Path::new("f0")).unwrap();
         assert!(run_check_tags_repo(&repo).is_ok());
-        assert!(run_sem_ver_repo(&repo, true, VersioningKindArg::Semver, Some("rs")).is_ok());
+        assert!(run_sem_ver_repo(
+            &repo,
+            "Cargo.toml",
+            None,
+            true,
+            VersioningKindArg::Semver,
+            BumpKindArg::Patch,
+            Some("rs")
+        )
+        .is_ok());
         assert_eq!(
             format!(
                 "{}",
-                run_sem_ver_repo(&repo, false, VersioningKindArg::Semver, None).unwrap_err()
+                run_sem_ver_repo(
+                    &repo,
+                    "Cargo.toml",
+                    None,
+                    false,
+                    VersioningKindArg::Semver,
+                    BumpKindArg::Patch,
+                    None
+                )
+                .unwrap_err()
             ),
             "Cargo version `0.1.0` is not up-to-date with repo `0.1.1-dev.1`".to_string()
         );
@@ -472,7 +1076,16 @@ mod tests {
         assert_eq!(
             format!(
                 "{}",
-                run_sem_ver_repo(&repo, false, VersioningKindArg::Semver, None).unwrap_err()
+                run_sem_ver_repo(
+                    &repo,
+                    "Cargo.toml",
+                    None,
+                    false,
+                    VersioningKindArg::Semver,
+                    BumpKindArg::Patch,
+                    None
+                )
+                .unwrap_err()
             ),
             "Cargo version `0.1.0` is not up-to-date with repo `0.1.1-dev.1`".to_string()
         );
@@ -480,4 +1093,437 @@ mod tests {
         let cargotoml = std::fs::read_to_string(td.path().join("Cargo.toml")).unwrap();
         assert!(cargotoml.contains("0.1.1-dev.1"));
     }
+
+    #[test]
+    fn test_dev_prerelease_counts_commits_not_tag_format() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (td, repo) = repo_init();
+        setup_repo(&td, &repo);
+        let mut index = repo.index().unwrap();
+        for n in 0..3 {
+            let name = format!("g{n}.rs");
+            File::create(td.path().join(&name))
+                .unwrap()
+                .write_all(name.as_bytes())
+                .unwrap();
+            index.add_path(Path::new(&name)).unwrap();
+            commit(&repo, &mut index, &format!("fix: change {n}"));
+        }
+        assert_eq!(
+            format!(
+                "{}",
+                run_sem_ver_repo(
+                    &repo,
+                    "Cargo.toml",
+                    None,
+                    false,
+                    VersioningKindArg::Semver,
+                    BumpKindArg::Patch,
+                    None
+                )
+                .unwrap_err()
+            ),
+            "Cargo version `0.1.0` is not up-to-date with repo `0.1.1-dev.3`".to_string()
+        );
+    }
+
+    #[test]
+    fn test_dev_prerelease_ignores_tags_own_prerelease_identifier() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (td, repo) = repo_init();
+        setup_repo_with_version(&td, &repo, "1.0.0-rc.1");
+        let mut index = repo.index().unwrap();
+        for n in 0..3 {
+            let name = format!("g{n}.rs");
+            File::create(td.path().join(&name))
+                .unwrap()
+                .write_all(name.as_bytes())
+                .unwrap();
+            index.add_path(Path::new(&name)).unwrap();
+            commit(&repo, &mut index, &format!("fix: change {n}"));
+        }
+        assert_eq!(
+            format!(
+                "{}",
+                run_sem_ver_repo(
+                    &repo,
+                    "Cargo.toml",
+                    None,
+                    false,
+                    VersioningKindArg::Semver,
+                    BumpKindArg::Patch,
+                    None
+                )
+                .unwrap_err()
+            ),
+            "Cargo version `1.0.0-rc.1` is not up-to-date with repo `1.0.1-dev.3`".to_string()
+        );
+    }
+
+    #[test]
+    fn test_conventional_bump_minor_on_feat() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (td, repo) = repo_init();
+        setup_repo_with_version(&td, &repo, "1.0.0");
+        let mut index = repo.index().unwrap();
+        File::create(&td.path().join("f0.rs"))
+            .unwrap()
+            .write_all("new".as_bytes())
+            .unwrap();
+        index.add_path(Path::new("f0.rs")).unwrap();
+        commit(&repo, &mut index, "fix: a small bugfix");
+        commit(&repo, &mut index, "feat: add a new feature");
+        assert_eq!(
+            format!(
+                "{}",
+                run_sem_ver_repo(
+                    &repo,
+                    "Cargo.toml",
+                    None,
+                    false,
+                    VersioningKindArg::Semver,
+                    BumpKindArg::Conventional,
+                    None
+                )
+                .unwrap_err()
+            ),
+            "Cargo version `1.0.0` is not up-to-date with repo `1.1.0-dev.2`".to_string()
+        );
+    }
+
+    #[test]
+    fn test_conventional_bump_major_on_breaking_change() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (td, repo) = repo_init();
+        setup_repo_with_version(&td, &repo, "1.0.0");
+        let mut index = repo.index().unwrap();
+        File::create(&td.path().join("f0.rs"))
+            .unwrap()
+            .write_all("new".as_bytes())
+            .unwrap();
+        index.add_path(Path::new("f0.rs")).unwrap();
+        commit(&repo, &mut index, "feat!: drop support for old config format");
+        assert_eq!(
+            format!(
+                "{}",
+                run_sem_ver_repo(
+                    &repo,
+                    "Cargo.toml",
+                    None,
+                    false,
+                    VersioningKindArg::Semver,
+                    BumpKindArg::Conventional,
+                    None
+                )
+                .unwrap_err()
+            ),
+            "Cargo version `1.0.0` is not up-to-date with repo `2.0.0-dev.1`".to_string()
+        );
+    }
+
+    #[test]
+    fn test_conventional_bump_on_0x_breaking_change_is_minor() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (td, repo) = repo_init();
+        setup_repo(&td, &repo);
+        let mut index = repo.index().unwrap();
+        File::create(&td.path().join("f0.rs"))
+            .unwrap()
+            .write_all("new".as_bytes())
+            .unwrap();
+        index.add_path(Path::new("f0.rs")).unwrap();
+        commit(&repo, &mut index, "feat!: drop support for old config format");
+        assert_eq!(
+            format!(
+                "{}",
+                run_sem_ver_repo(
+                    &repo,
+                    "Cargo.toml",
+                    None,
+                    false,
+                    VersioningKindArg::Semver,
+                    BumpKindArg::Conventional,
+                    None
+                )
+                .unwrap_err()
+            ),
+            "Cargo version `0.1.0` is not up-to-date with repo `0.2.0-dev.1`".to_string()
+        );
+    }
+
+    #[test]
+    fn test_changelog_groups_by_type_and_unreleased() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (td, repo) = repo_init();
+        setup_repo(&td, &repo);
+        let mut index = repo.index().unwrap();
+        File::create(&td.path().join("f0.rs"))
+            .unwrap()
+            .write_all("new".as_bytes())
+            .unwrap();
+        index.add_path(Path::new("f0.rs")).unwrap();
+        commit(&repo, &mut index, "feat(cli): add changelog subcommand");
+        commit(&repo, &mut index, "fix: correct tag parsing");
+        commit(&repo, &mut index, "chore: tidy up imports");
+
+        let changelog = render_changelog(&repo, None).unwrap();
+        assert!(changelog.starts_with("## Unreleased\n\n"));
+        assert!(changelog.contains("### Features\n\n- add changelog subcommand"));
+        assert!(changelog.contains("### Bug Fixes\n\n- correct tag parsing"));
+        assert!(changelog.contains("### Chore\n\n- tidy up imports"));
+        assert!(changelog.contains("## 0.1.0 -"));
+    }
+
+    #[test]
+    fn test_changelog_scope_filter() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (td, repo) = repo_init();
+        setup_repo(&td, &repo);
+        let mut index = repo.index().unwrap();
+        File::create(&td.path().join("f0.rs"))
+            .unwrap()
+            .write_all("new".as_bytes())
+            .unwrap();
+        index.add_path(Path::new("f0.rs")).unwrap();
+        commit(&repo, &mut index, "feat(cli): add changelog subcommand");
+        commit(&repo, &mut index, "fix(core): correct tag parsing");
+
+        let changelog = render_changelog(&repo, Some("cli")).unwrap();
+        assert!(changelog.contains("add changelog subcommand"));
+        assert!(!changelog.contains("correct tag parsing"));
+    }
+
+    fn setup_workspace_repo(td: &TempDir, repo: &Repository) {
+        let mut index = repo.index().unwrap();
+        let root_contents = "[workspace]\nmembers = [\"crates/a\", \"crates/b\"]\n";
+        File::create(&td.path().join("Cargo.toml"))
+            .unwrap()
+            .write_all(root_contents.as_bytes())
+            .unwrap();
+        index.add_path(Path::new("Cargo.toml")).unwrap();
+
+        for member in ["a", "b"] {
+            let member_dir = td.path().join("crates").join(member);
+            std::fs::create_dir_all(&member_dir).unwrap();
+            let manifest = format!(
+                "[package]\nname = \"{}\"\nversion = \"0.1.0\"\n",
+                member
+            );
+            File::create(member_dir.join("Cargo.toml"))
+                .unwrap()
+                .write_all(manifest.as_bytes())
+                .unwrap();
+            index
+                .add_path(&Path::new("crates").join(member).join("Cargo.toml"))
+                .unwrap();
+
+            let src_file = format!("crates/{}/lib.rs", member);
+            File::create(td.path().join(&src_file))
+                .unwrap()
+                .write_all(b"// lib")
+                .unwrap();
+            index.add_path(Path::new(&src_file)).unwrap();
+        }
+
+        commit(repo, &mut index, "set up workspace");
+        let sig = repo.signature().unwrap();
+        for member in ["a", "b"] {
+            repo.tag(
+                &format!("{}-v0.1.0", member),
+                &repo.revparse_single("HEAD").unwrap(),
+                &sig,
+                "initial version",
+                false,
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_workspace_bump_uses_per_crate_tag_namespace() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (td, repo) = repo_init();
+        setup_workspace_repo(&td, &repo);
+        let mut index = repo.index().unwrap();
+        File::create(td.path().join("crates/a/lib.rs"))
+            .unwrap()
+            .write_all(b"// lib, changed")
+            .unwrap();
+        index.add_path(Path::new("crates/a/lib.rs")).unwrap();
+        commit(&repo, &mut index, "fix: bump crate a only");
+
+        let err = run_sem_ver_workspace(
+            &repo,
+            &[],
+            false,
+            VersioningKindArg::Semver,
+            BumpKindArg::Patch,
+        )
+        .unwrap_err();
+        assert_eq!(
+            format!("{}", err),
+            "Cargo version `0.1.0` is not up-to-date with repo `0.1.1-dev.1`".to_string()
+        );
+
+        let crate_a = std::fs::read_to_string(td.path().join("crates/a/Cargo.toml")).unwrap();
+        assert!(crate_a.contains("0.1.1-dev.1"));
+        let crate_b = std::fs::read_to_string(td.path().join("crates/b/Cargo.toml")).unwrap();
+        assert!(crate_b.contains("version = \"0.1.0\""));
+    }
+
+    #[test]
+    fn test_workspace_bump_path_selects_single_member() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (td, repo) = repo_init();
+        setup_workspace_repo(&td, &repo);
+        let mut index = repo.index().unwrap();
+        for member in ["a", "b"] {
+            let src_file = format!("crates/{}/lib.rs", member);
+            File::create(td.path().join(&src_file))
+                .unwrap()
+                .write_all(b"// lib, changed")
+                .unwrap();
+            index.add_path(Path::new(&src_file)).unwrap();
+        }
+        commit(&repo, &mut index, "fix: touch both crates");
+
+        let result = run_sem_ver_workspace(
+            &repo,
+            &["crates/b".to_string()],
+            false,
+            VersioningKindArg::Semver,
+            BumpKindArg::Patch,
+        );
+        assert!(result.is_err());
+
+        let crate_a = std::fs::read_to_string(td.path().join("crates/a/Cargo.toml")).unwrap();
+        assert!(crate_a.contains("version = \"0.1.0\""));
+        let crate_b = std::fs::read_to_string(td.path().join("crates/b/Cargo.toml")).unwrap();
+        assert!(crate_b.contains("0.1.1-dev.1"));
+    }
+
+    #[test]
+    fn test_get_workspace_members_ignores_default_members() {
+        let root_manifest =
+            "[workspace]\ndefault-members = [\"crates/z\"]\nmembers = [\"crates/a\", \"crates/b\"]\n";
+        assert_eq!(
+            get_workspace_members(root_manifest).unwrap(),
+            vec!["crates/a".to_string(), "crates/b".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_workspace_staged_change_does_not_dirty_other_members() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (td, repo) = repo_init();
+        setup_workspace_repo(&td, &repo);
+        let mut index = repo.index().unwrap();
+        File::create(td.path().join("crates/a/lib.rs"))
+            .unwrap()
+            .write_all(b"// lib, changed")
+            .unwrap();
+        index.add_path(Path::new("crates/a/lib.rs")).unwrap();
+        // Staged but not committed, to exercise the dirty-state path rather
+        // than the committed-change path.
+
+        let err = run_sem_ver_workspace(
+            &repo,
+            &[],
+            false,
+            VersioningKindArg::Semver,
+            BumpKindArg::Patch,
+        )
+        .unwrap_err();
+        assert_eq!(
+            format!("{}", err),
+            "Cargo version `0.1.0` is not up-to-date with repo `0.1.1-dev.1`".to_string()
+        );
+
+        let crate_a = std::fs::read_to_string(td.path().join("crates/a/Cargo.toml")).unwrap();
+        assert!(crate_a.contains("0.1.1-dev.1"));
+        let crate_b = std::fs::read_to_string(td.path().join("crates/b/Cargo.toml")).unwrap();
+        assert!(crate_b.contains("version = \"0.1.0\""));
+    }
+
+    #[test]
+    fn test_workspace_conventional_bump_scoped_to_member() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let (td, repo) = repo_init();
+        setup_workspace_repo(&td, &repo);
+        let mut index = repo.index().unwrap();
+        File::create(td.path().join("crates/a/lib.rs"))
+            .unwrap()
+            .write_all(b"// lib, breaking change")
+            .unwrap();
+        index.add_path(Path::new("crates/a/lib.rs")).unwrap();
+        commit(&repo, &mut index, "feat!: break crate a only");
+        File::create(td.path().join("crates/b/lib.rs"))
+            .unwrap()
+            .write_all(b"// lib, small fix")
+            .unwrap();
+        index.add_path(Path::new("crates/b/lib.rs")).unwrap();
+        commit(&repo, &mut index, "fix: small fix to crate b only");
+
+        let _ = run_sem_ver_workspace(
+            &repo,
+            &[],
+            false,
+            VersioningKindArg::Semver,
+            BumpKindArg::Conventional,
+        );
+
+        // Crate a's own commit is a 0.x breaking change, so it's demoted to
+        // a minor bump. Crate b only saw a fix, and must not inherit crate
+        // a's breaking-change severity or commit count.
+        let crate_a = std::fs::read_to_string(td.path().join("crates/a/Cargo.toml")).unwrap();
+        assert!(crate_a.contains("0.2.0-dev.1"));
+        let crate_b = std::fs::read_to_string(td.path().join("crates/b/Cargo.toml")).unwrap();
+        assert!(crate_b.contains("0.1.1-dev.1"));
+    }
+
+    #[test]
+    fn test_parse_cargo_version_rejects_workspace_inherited() {
+        let contents = "[package]\nname = \"test package\"\nversion.workspace = true\n";
+        let err = parse_cargo_version(contents).unwrap_err();
+        assert!(err.contains("workspace root Cargo.toml"));
+
+        let contents = "[package]\nname = \"test package\"\nversion = { workspace = true }\n";
+        let err = parse_cargo_version(contents).unwrap_err();
+        assert!(err.contains("workspace root Cargo.toml"));
+    }
+
+    #[test]
+    fn test_replace_version_preserves_comments() {
+        let (td, _repo) = repo_init();
+        let cargo_contents = "# leading comment\n[package]\nname = \"test package\"\nversion = \"0.1.0\" # current version\n\n[dependencies]\n";
+        let cargotoml_path = td.path().join("Cargo.toml");
+        File::create(&cargotoml_path)
+            .unwrap()
+            .write_all(cargo_contents.as_bytes())
+            .unwrap();
+
+        replace_version(cargotoml_path.to_str().unwrap(), "0.2.0").unwrap();
+
+        let updated = std::fs::read_to_string(&cargotoml_path).unwrap();
+        assert!(updated.contains("# leading comment"));
+        assert!(updated.contains("version = \"0.2.0\" # current version"));
+        assert!(updated.contains("[dependencies]"));
+    }
+
+    #[test]
+    fn test_replace_version_rejects_workspace_inherited() {
+        let (td, _repo) = repo_init();
+        let cargo_contents = "[package]\nname = \"test package\"\nversion.workspace = true\n";
+        let cargotoml_path = td.path().join("Cargo.toml");
+        File::create(&cargotoml_path)
+            .unwrap()
+            .write_all(cargo_contents.as_bytes())
+            .unwrap();
+
+        let err = replace_version(cargotoml_path.to_str().unwrap(), "0.2.0").unwrap_err();
+        assert!(format!("{}", err).contains("workspace root Cargo.toml"));
+
+        let unchanged = std::fs::read_to_string(&cargotoml_path).unwrap();
+        assert!(unchanged.contains("version.workspace = true"));
+    }
 }